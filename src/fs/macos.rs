@@ -7,22 +7,38 @@ use std::{
     ptr::{self, NonNull},
 };
 
-use crate::{DeviceId, DeviceKind, StorageDevice, StorageEvent, StorageVolume, VolumeId};
+use std::sync::Mutex;
+
+use crate::{
+    DeviceId, DeviceKind, MediaType, MountDecision, SmartHealth, StorageDevice, StorageEvent,
+    StorageVolume, VolumeId,
+};
 use anyhow::Context;
 use libc::c_void;
 use objc2_core_foundation::{
     CFBoolean, CFDictionary, CFNumber, CFRetained, CFRunLoop, CFString, CFURL, CFUUID, Type,
     kCFRunLoopDefaultMode,
 };
+use objc2_io_kit::{
+    IOBSDNameMatching, IOObjectRelease, IORegistryEntrySearchCFProperty,
+    IOServiceGetMatchingService, kIOMainPortDefault, kIORegistryIterateParents,
+    kIORegistryIterateRecursively,
+};
 use objc2_disk_arbitration::{
-    DADisk, DARegisterDiskAppearedCallback, DARegisterDiskDescriptionChangedCallback,
-    DARegisterDiskDisappearedCallback, DASession, kDADiskDescriptionDeviceInternalKey,
+    DADisk, DADiskEject, DADiskMount, DADiskUnmount, DADissenter, DADissenterCreate,
+    DADissenterGetStatus, DARegisterDiskAppearedCallback,
+    DARegisterDiskDescriptionChangedCallback, DARegisterDiskDisappearedCallback,
+    DARegisterDiskMountApprovalCallback, DASession, kDADiskDescriptionDeviceInternalKey,
     kDADiskDescriptionDeviceModelKey, kDADiskDescriptionDevicePathKey,
+    kDADiskDescriptionDeviceVendorKey, kDADiskDescriptionMediaBSDNameKey,
     kDADiskDescriptionMediaEjectableKey, kDADiskDescriptionMediaNameKey,
     kDADiskDescriptionMediaRemovableKey, kDADiskDescriptionMediaSizeKey,
     kDADiskDescriptionMediaUUIDKey, kDADiskDescriptionMediaWritableKey,
-    kDADiskDescriptionVolumeNameKey, kDADiskDescriptionVolumePathKey,
+    kDADiskDescriptionVolumeKindKey, kDADiskDescriptionVolumeNameKey,
+    kDADiskDescriptionVolumePathKey,
     kDADiskDescriptionVolumeUUIDKey, kDADiskDescriptionWatchVolumePath,
+    kDADiskEjectOptionDefault, kDADiskMountOptionDefault, kDADiskUnmountOptionDefault,
+    kDADiskUnmountOptionForce, kDAReturnNotPermitted,
 };
 use tracing::trace;
 
@@ -30,6 +46,22 @@ struct CallbackContext {
     tx: flume::Sender<StorageEvent>,
 }
 
+type MountApprovalPredicate = Box<dyn Fn(&StorageVolume) -> MountDecision + Send>;
+
+/// Predicate consulted by the Disk Arbitration mount-approval callback. Set via
+/// [`register_mount_approval`] and read from the run loop driving
+/// [`monitor_devices`].
+static MOUNT_APPROVAL: Mutex<Option<MountApprovalPredicate>> = Mutex::new(None);
+
+/// Register a predicate that is asked to approve each volume before it is
+/// mounted. Returning [`MountDecision::Deny`] blocks the mount and reports the
+/// reason to the system. A [`monitor_devices`] session only checks mounts if a
+/// predicate was already registered when it started; replacing the predicate
+/// later also changes the one that running sessions consult.
+pub fn register_mount_approval(predicate: impl Fn(&StorageVolume) -> MountDecision + Send + 'static) {
+    *MOUNT_APPROVAL.lock().unwrap() = Some(Box::new(predicate));
+}
+
 pub fn get_devices() -> anyhow::Result<(Vec<StorageDevice>, Vec<StorageVolume>)> {
     trace!("getting current devices and volumes");
     let session = unsafe { DASession::new(None).context("could not create disk session") }?;
@@ -101,7 +133,17 @@ pub fn get_devices() -> anyhow::Result<(Vec<StorageDevice>, Vec<StorageVolume>)>
         let disk_desc: &CFDictionary<CFString> = unsafe { disk_desc.cast_unchecked() };
 
         // Get volume info
-        let volume = get_volume_info(disk_desc);
+        let mut volume = get_volume_info(disk_desc);
+
+        // Prefer the filesystem name reported by the kernel over the Disk
+        // Arbitration volume kind, which is not always populated.
+        let fs_type = unsafe { CStr::from_ptr(fs.f_fstypename.as_ptr()) };
+        if let Ok(fs_type) = fs_type.to_str() {
+            if !fs_type.is_empty() {
+                volume.file_system = Some(fs_type.to_owned());
+            }
+        }
+
         trace!("got volume: {:?}", volume);
 
         // Get device info if we haven't seen this device before
@@ -165,6 +207,17 @@ pub fn monitor_devices(tx: flume::Sender<StorageEvent>) -> anyhow::Result<()> {
         )
     };
 
+    if MOUNT_APPROVAL.lock().unwrap().is_some() {
+        unsafe {
+            DARegisterDiskMountApprovalCallback(
+                &session,
+                None,
+                Some(callbacks::mount_approval),
+                ptr::null_mut(),
+            )
+        };
+    }
+
     CFRunLoop::run();
 
     unsafe { session.unschedule_from_run_loop(&run_loop, kCFRunLoopDefaultMode.unwrap()) };
@@ -175,13 +228,119 @@ pub fn monitor_devices(tx: flume::Sender<StorageEvent>) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Shared state for a single in-flight Disk Arbitration control call. The
+/// completion callback records the resulting status and stops the run loop that
+/// [`execute_da`] is blocked on.
+struct ControlContext {
+    status: i32,
+    run_loop: CFRetained<CFRunLoop>,
+}
+
+/// Completion callback shared by unmount, mount and eject. A null dissenter
+/// means the operation succeeded; otherwise it carries the failure status.
+unsafe extern "C-unwind" fn control_callback(
+    _disk: NonNull<DADisk>,
+    dissenter: *mut DADissenter,
+    context: *mut c_void,
+) {
+    let context = unsafe { &mut *(context as *mut ControlContext) };
+    context.status = match NonNull::new(dissenter) {
+        None => 0, // kDAReturnSuccess
+        Some(dissenter) => unsafe { DADissenterGetStatus(dissenter.as_ref()) },
+    };
+    CFRunLoop::stop(&context.run_loop);
+}
+
+/// Resolve `bsd_name` to a [`DADisk`], invoke an asynchronous Disk Arbitration
+/// operation against it, and block on the calling thread's run loop until the completion
+/// callback fires. The `DADissenter` status is surfaced as the returned error.
+fn execute_da<F>(bsd_name: &str, invoke: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&DADisk, *mut c_void),
+{
+    let session = unsafe { DASession::new(None).context("could not create disk session") }?;
+    let run_loop = CFRunLoop::current().context("could not get current run loop")?;
+
+    unsafe { session.schedule_with_run_loop(&run_loop, kCFRunLoopDefaultMode.unwrap()) };
+
+    let bsd_name_c = CString::new(bsd_name).context("BSD name contained a NUL byte")?;
+    let disk = unsafe {
+        DADisk::from_bsd_name(
+            None,
+            &session,
+            NonNull::new(bsd_name_c.as_ptr() as *mut _).unwrap(),
+        )
+    }
+    .with_context(|| format!("no disk matches BSD name {bsd_name:?}"))?;
+
+    let mut context = ControlContext {
+        status: 0,
+        run_loop: run_loop.clone(),
+    };
+
+    invoke(&disk, &mut context as *mut ControlContext as *mut c_void);
+
+    CFRunLoop::run();
+
+    unsafe { session.unschedule_from_run_loop(&run_loop, kCFRunLoopDefaultMode.unwrap()) };
+
+    if context.status == 0 {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Disk Arbitration returned status {:#010x}",
+            context.status as u32
+        ))
+    }
+}
+
+/// Unmount every filesystem on the volume named by `bsd_name` (the
+/// [`StorageVolume::bsd_name`], e.g. `"disk2s1"`). When `force` is set the
+/// unmount proceeds even if files are still open on the volume.
+pub fn unmount_volume(bsd_name: &str, force: bool) -> anyhow::Result<()> {
+    let options = if force {
+        kDADiskUnmountOptionForce
+    } else {
+        kDADiskUnmountOptionDefault
+    };
+
+    execute_da(bsd_name, |disk, context| unsafe {
+        DADiskUnmount(disk, options, Some(control_callback), context)
+    })
+}
+
+/// Mount the volume named by `bsd_name` (the [`StorageVolume::bsd_name`], e.g.
+/// `"disk2s1"`) at its default mount point.
+pub fn mount_volume(bsd_name: &str) -> anyhow::Result<()> {
+    execute_da(bsd_name, |disk, context| unsafe {
+        DADiskMount(
+            disk,
+            None,
+            kDADiskMountOptionDefault,
+            Some(control_callback),
+            context,
+        )
+    })
+}
+
+/// Eject the device named by `bsd_name` (the whole-disk
+/// [`StorageDevice::bsd_name`], e.g. `"disk2"`), powering down removable media
+/// where the hardware supports it.
+pub fn eject_device(bsd_name: &str) -> anyhow::Result<()> {
+    execute_da(bsd_name, |disk, context| unsafe {
+        DADiskEject(disk, kDADiskEjectOptionDefault, Some(control_callback), context)
+    })
+}
+
 mod callbacks {
-    use std::{ffi::c_void, ptr::NonNull};
+    use std::{ffi::c_void, ptr, ptr::NonNull};
 
-    use objc2_core_foundation::{CFArray, CFDictionary, CFString};
-    use objc2_disk_arbitration::DADisk;
+    use objc2_core_foundation::{CFArray, CFDictionary, CFRetained, CFString};
+    use objc2_disk_arbitration::{
+        DADisk, DADissenter, DADissenterCreate, kDAReturnNotPermitted,
+    };
 
-    use crate::StorageEvent;
+    use crate::{MountDecision, StorageEvent};
 
     pub unsafe extern "C-unwind" fn disk_appeared(disk: NonNull<DADisk>, context: *mut c_void) {
         let disk_desc = unsafe { disk.as_ref().description() };
@@ -221,6 +380,39 @@ mod callbacks {
         let _ = context.tx.send(StorageEvent::AddVolume { volume });
     }
 
+    /// Disk Arbitration asks this before mounting a volume. A null return
+    /// approves the mount; a non-null [`DADissenter`] blocks it. We build a
+    /// [`StorageVolume`] from the disk description and consult the registered
+    /// predicate, if any.
+    pub unsafe extern "C-unwind" fn mount_approval(
+        disk: NonNull<DADisk>,
+        _context: *mut c_void,
+    ) -> *mut DADissenter {
+        let disk_desc = unsafe { disk.as_ref().description() };
+        let Some(disk_desc) = disk_desc else {
+            return ptr::null_mut();
+        };
+
+        let disk_desc: &CFDictionary<CFString> = unsafe { disk_desc.cast_unchecked() };
+        let volume = super::get_volume_info(disk_desc);
+
+        let guard = super::MOUNT_APPROVAL.lock().unwrap();
+        let Some(predicate) = guard.as_ref() else {
+            return ptr::null_mut();
+        };
+
+        match predicate(&volume) {
+            MountDecision::Allow => ptr::null_mut(),
+            MountDecision::Deny { reason } => {
+                let reason = CFString::from_str(&reason);
+                let dissenter =
+                    unsafe { DADissenterCreate(None, kDAReturnNotPermitted, Some(&reason)) };
+                // Hand ownership of the +1 reference to Disk Arbitration.
+                CFRetained::into_raw(dissenter).as_ptr()
+            }
+        }
+    }
+
     pub unsafe extern "C-unwind" fn disk_disappeared(disk: NonNull<DADisk>, context: *mut c_void) {
         let disk_desc = unsafe { disk.as_ref().description() };
         let Some(disk_desc) = disk_desc else {
@@ -305,6 +497,14 @@ fn get_volume_info(disk_desc: &CFDictionary<CFString>) -> StorageVolume {
         unsafe { get_from_dict::<CFBoolean>(disk_desc, kDADiskDescriptionMediaWritableKey) };
     let media_writable = media_writable.map(|writable| writable.as_bool());
 
+    let file_system =
+        unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionVolumeKindKey) };
+    let file_system = file_system.map(|kind| kind.to_string());
+
+    let bsd_name =
+        unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionMediaBSDNameKey) };
+    let bsd_name = bsd_name.map(|name| name.to_string());
+
     StorageVolume {
         id: volume_id,
         device_id: device_path.clone().map(|path| DeviceId(path)),
@@ -312,13 +512,89 @@ fn get_volume_info(disk_desc: &CFDictionary<CFString>) -> StorageVolume {
         size,
         free,
         path: device_path.clone().map(PathBuf::from),
+        bsd_name,
         mounts: mount_path.into_iter().collect(),
+        file_system,
         partition_id: None, // Could potentially get from BSD name
         is_writable: media_writable,
         is_system: None,
     }
 }
 
+/// Resolve the SSD/HDD medium type for a disk from its BSD name by walking the
+/// IOKit registry up to the backing media object and reading the `Medium Type`
+/// entry of its `Device Characteristics` dictionary. Mirrors how `sysinfo`
+/// derives `DiskKind` on macOS.
+fn media_type_for_bsd_name(bsd_name: &str) -> Option<MediaType> {
+    let bsd_name = CString::new(bsd_name).ok()?;
+
+    // IOServiceGetMatchingService consumes a reference to the matching dict.
+    let matching = unsafe { IOBSDNameMatching(kIOMainPortDefault, 0, bsd_name.as_ptr()) }?;
+    let service = unsafe { IOServiceGetMatchingService(kIOMainPortDefault, Some(&matching)) };
+    if service == 0 {
+        return None;
+    }
+
+    let characteristics = unsafe {
+        IORegistryEntrySearchCFProperty(
+            service,
+            c"IOService".as_ptr(),
+            &CFString::from_static_str("Device Characteristics"),
+            None,
+            kIORegistryIterateRecursively | kIORegistryIterateParents,
+        )
+    };
+
+    unsafe { IOObjectRelease(service) };
+
+    let characteristics: CFRetained<CFDictionary<CFString>> = characteristics?.downcast().ok()?;
+    let medium = unsafe {
+        get_from_dict::<CFString>(&characteristics, &CFString::from_static_str("Medium Type"))
+    }?;
+
+    Some(match medium.to_string().as_str() {
+        "Solid State" => MediaType::SolidState,
+        "Rotational" => MediaType::Rotational,
+        _ => MediaType::Unknown,
+    })
+}
+
+/// Reduce a slice BSD name such as `"disk2s1"` to its whole-disk name
+/// (`"disk2"`), leaving an already-whole name untouched. Disk Arbitration
+/// reports the slice name for a mounted volume, but control and SMART queries
+/// must address the backing whole disk.
+fn whole_disk_bsd_name(bsd_name: &str) -> &str {
+    match bsd_name.strip_prefix("disk") {
+        // Drop everything from the first slice separator (`sN`) onwards.
+        Some(rest) => match rest.find('s') {
+            Some(i) => &bsd_name[.."disk".len() + i],
+            None => bsd_name,
+        },
+        None => bsd_name,
+    }
+}
+
+/// Join device vendor, product and media name into a single human-friendly
+/// label: whitespace is trimmed, runs of spaces collapse to one, and empty or
+/// duplicate adjacent tokens are dropped. This is the "JoinName" behaviour
+/// Chromium uses for its storage monitors, turning a bare model code into
+/// something like `"SanDisk Cruzer"`.
+fn join_display_name(parts: &[Option<&str>]) -> Option<String> {
+    let mut tokens: Vec<&str> = Vec::new();
+
+    for token in parts.iter().flatten().flat_map(|part| part.split_whitespace()) {
+        if tokens.last().map(|last| last.eq_ignore_ascii_case(token)) != Some(true) {
+            tokens.push(token);
+        }
+    }
+
+    if tokens.is_empty() {
+        None
+    } else {
+        Some(tokens.join(" "))
+    }
+}
+
 fn get_device_info(disk_desc: &CFDictionary<CFString>) -> StorageDevice {
     let device_path =
         unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionDevicePathKey) };
@@ -331,9 +607,26 @@ fn get_device_info(disk_desc: &CFDictionary<CFString>) -> StorageDevice {
         .and_then(|serial| CFUUID::new_string(None, Some(&serial)))
         .map(|serial| serial.to_string());
 
-    let display_name =
+    let media_name =
         unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionMediaNameKey) };
-    let display_name = display_name.map(|name| name.to_string());
+    let media_name = media_name.map(|name| name.to_string());
+
+    let device_vendor =
+        unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionDeviceVendorKey) };
+    let device_vendor = device_vendor.map(|vendor| vendor.to_string());
+
+    let device_model =
+        unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionDeviceModelKey) };
+    let device_model = device_model.map(|model| model.to_string());
+
+    // Media names are frequently empty or generic ("Flash Disk"), so compose a
+    // friendlier label from the vendor and product where they are available.
+    let display_name = join_display_name(&[
+        device_vendor.as_deref(),
+        device_model.as_deref(),
+        media_name.as_deref(),
+    ])
+    .or(media_name);
 
     let is_internal =
         unsafe { get_from_dict::<CFBoolean>(disk_desc, kDADiskDescriptionDeviceInternalKey) };
@@ -347,11 +640,34 @@ fn get_device_info(disk_desc: &CFDictionary<CFString>) -> StorageDevice {
         unsafe { get_from_dict::<CFBoolean>(disk_desc, kDADiskDescriptionMediaEjectableKey) };
     let is_ejectable = is_ejectable.map(|ejectable| ejectable.as_bool());
 
-    let device_model =
-        unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionDeviceModelKey) };
-    let device_model = device_model.map(|model| model.to_string());
+    let bsd_name =
+        unsafe { get_from_dict::<CFString>(disk_desc, kDADiskDescriptionMediaBSDNameKey) };
+    // Disk Arbitration reports the slice name (e.g. `disk2s1`) when a mounted
+    // volume is enumerated; reduce it to the whole-disk name the control and
+    // SMART APIs expect.
+    let bsd_name = bsd_name.map(|name| whole_disk_bsd_name(&name.to_string()).to_owned());
+    let media_type = bsd_name
+        .as_deref()
+        .and_then(media_type_for_bsd_name);
+
+    // A mounted disk image reports its model as "Disk Image"; callers use this
+    // to filter DMGs out of lists of "real" removable hardware.
+    let is_disk_image = device_model.as_deref().map(|model| model == "Disk Image");
+
+    // Removable media exposing a top-level DCIM directory is treated as a
+    // camera, matching the heuristic Chromium's `GetDeviceType` uses.
+    let has_dcim = {
+        let mount_path =
+            unsafe { get_from_dict::<CFURL>(disk_desc, kDADiskDescriptionVolumePathKey) };
+        mount_path
+            .and_then(|path| path.to_file_path())
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(|path| path.join("DCIM").is_dir())
+            .unwrap_or(false)
+    };
 
     let kind = match device_model.as_deref() {
+        _ if has_dcim && is_removable == Some(true) => DeviceKind::Camera,
         Some("SD/MMC") => DeviceKind::SdCard,
         Some("Micro SD/M2") => DeviceKind::MicroSdCard,
         Some("Flash Disk") => DeviceKind::UsbFlashDrive,
@@ -367,10 +683,223 @@ fn get_device_info(disk_desc: &CFDictionary<CFString>) -> StorageDevice {
         display_name: display_name,
         model: device_model,
         kind: kind,
+        media_type,
         internal: is_internal,
         removable: is_removable,
         ejectable: is_ejectable,
+        is_disk_image,
+        bsd_name,
         serial: device_serial,
         volumes: HashSet::new(), // Will be populated when processing volumes
     }
 }
+
+/// Query the SMART self-assessment of the device named by `bsd_name` (the
+/// whole-disk [`StorageDevice::bsd_name`], e.g. `"disk0"`). The name is resolved
+/// to an IOKit service and interrogated through the ATA SMART user-client
+/// plugin. Controllers that do not expose SMART (USB bridges are the common
+/// case) yield a [`SmartHealth`] with every field left as `None` rather than an
+/// error.
+pub fn device_health(bsd_name: &str) -> anyhow::Result<SmartHealth> {
+    let bsd_name_c = CString::new(bsd_name).context("BSD name contained a NUL byte")?;
+
+    let matching = unsafe { IOBSDNameMatching(kIOMainPortDefault, 0, bsd_name_c.as_ptr()) }
+        .context("could not build IOKit matching dictionary")?;
+    let service = unsafe { IOServiceGetMatchingService(kIOMainPortDefault, Some(&matching)) };
+    if service == 0 {
+        anyhow::bail!("no IOKit service matches BSD name {bsd_name:?}");
+    }
+
+    let health = unsafe { smart::read_health(service) };
+    unsafe { IOObjectRelease(service) };
+
+    Ok(health.unwrap_or_default())
+}
+
+mod smart {
+    //! Minimal bindings to the ATA SMART user-client plugin. IOKit only exposes
+    //! SMART data through a CFPlugIn COM-style interface that the higher-level
+    //! `objc2-io-kit` bindings do not cover, so the handful of vtable entries we
+    //! need are declared here by hand.
+
+    use std::ffi::c_void;
+    use std::ptr;
+
+    use objc2_core_foundation::{
+        CFUUID, CFUUIDBytes, CFUUIDGetConstantUUIDWithBytes, CFUUIDGetUUIDBytes, CFUUIDRef,
+    };
+
+    use crate::SmartHealth;
+
+    type IOReturn = i32;
+    type SInt32 = i32;
+    type HResult = i32;
+    type Boolean = u8;
+    type IoService = u32;
+
+    #[repr(C)]
+    struct IOCFPlugInInterface {
+        _reserved: *mut c_void,
+        query_interface:
+            unsafe extern "C" fn(*mut c_void, CFUUIDBytes, *mut *mut c_void) -> HResult,
+        add_ref: unsafe extern "C" fn(*mut c_void) -> u32,
+        release: unsafe extern "C" fn(*mut c_void) -> u32,
+        version: u16,
+        revision: u16,
+        probe: unsafe extern "C" fn(*mut c_void, *const c_void, IoService, *mut SInt32) -> IOReturn,
+        start: unsafe extern "C" fn(*mut c_void, *const c_void, IoService) -> IOReturn,
+        stop: unsafe extern "C" fn(*mut c_void) -> IOReturn,
+    }
+
+    /// The 512-byte vendor-specific SMART data log returned by `SMARTReadData`.
+    #[repr(C)]
+    struct ATASmartData {
+        data: [u8; 512],
+    }
+
+    // Only the leading vtable entries up to `smart_read_data` are declared; the
+    // trailing log/identify accessors are unused and intentionally omitted.
+    #[repr(C)]
+    struct IOATASMARTInterface {
+        _reserved: *mut c_void,
+        query_interface:
+            unsafe extern "C" fn(*mut c_void, CFUUIDBytes, *mut *mut c_void) -> HResult,
+        add_ref: unsafe extern "C" fn(*mut c_void) -> u32,
+        release: unsafe extern "C" fn(*mut c_void) -> u32,
+        version: u16,
+        revision: u16,
+        smart_enable_disable_operations: unsafe extern "C" fn(*mut c_void, Boolean) -> IOReturn,
+        smart_enable_disable_auto_save: unsafe extern "C" fn(*mut c_void, Boolean) -> IOReturn,
+        smart_return_status: unsafe extern "C" fn(*mut c_void, *mut Boolean) -> IOReturn,
+        smart_execute_off_line_immediate: unsafe extern "C" fn(*mut c_void, Boolean) -> IOReturn,
+        smart_read_data: unsafe extern "C" fn(*mut c_void, *mut ATASmartData) -> IOReturn,
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOCreatePlugInInterfaceForService(
+            service: IoService,
+            plugin_type: CFUUIDRef,
+            interface_type: CFUUIDRef,
+            the_interface: *mut *mut *mut IOCFPlugInInterface,
+            the_score: *mut SInt32,
+        ) -> IOReturn;
+    }
+
+    /// `kIOATASMARTUserClientTypeID` from `<IOKit/storage/ata/ATASMARTLib.h>`.
+    unsafe fn user_client_type_id() -> Option<objc2_core_foundation::CFRetained<CFUUID>> {
+        unsafe {
+            CFUUIDGetConstantUUIDWithBytes(
+                None, 0x24, 0x70, 0x96, 0xD6, 0xFA, 0x6F, 0x11, 0xD4, 0x94, 0x36, 0x00, 0x05, 0x02,
+                0x8F, 0x18, 0xD5,
+            )
+        }
+    }
+
+    /// `kIOCFPlugInInterfaceID` from `<IOKit/IOCFPlugIn.h>`.
+    unsafe fn plugin_interface_id() -> Option<objc2_core_foundation::CFRetained<CFUUID>> {
+        unsafe {
+            CFUUIDGetConstantUUIDWithBytes(
+                None, 0xC2, 0x44, 0xE8, 0x58, 0x10, 0x9C, 0x11, 0xD4, 0x91, 0xD4, 0x00, 0x50, 0xE4,
+                0xC6, 0x42, 0x6F,
+            )
+        }
+    }
+
+    /// `kIOATASMARTInterfaceID` from `<IOKit/storage/ata/ATASMARTLib.h>`.
+    unsafe fn ata_smart_interface_id() -> Option<objc2_core_foundation::CFRetained<CFUUID>> {
+        unsafe {
+            CFUUIDGetConstantUUIDWithBytes(
+                None, 0x75, 0x19, 0xD5, 0x50, 0xFA, 0x71, 0x11, 0xD4, 0x98, 0xE3, 0x00, 0x05, 0x02,
+                0x8F, 0x18, 0xD5,
+            )
+        }
+    }
+
+    /// Decode the vendor-specific attribute table (30 twelve-byte entries
+    /// starting at offset 2) into the handful of attributes we surface.
+    fn parse_attributes(data: &[u8; 512], health: &mut SmartHealth) {
+        for entry in data[2..2 + 30 * 12].chunks_exact(12) {
+            let id = entry[0];
+            if id == 0 {
+                continue;
+            }
+
+            // The raw value is a little-endian 48-bit field.
+            let raw = entry[5..11]
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, &byte)| acc | ((byte as u64) << (8 * i)));
+
+            match id {
+                5 => health.reallocated_sectors = Some(raw),
+                9 => health.power_on_hours = Some(raw),
+                190 | 194 => health.temperature_celsius = Some((raw & 0xff) as u32),
+                _ => {}
+            }
+        }
+    }
+
+    pub unsafe fn read_health(service: IoService) -> Option<SmartHealth> {
+        let type_id = unsafe { user_client_type_id() }?;
+        let plugin_id = unsafe { plugin_interface_id() }?;
+
+        let mut plugin: *mut *mut IOCFPlugInInterface = ptr::null_mut();
+        let mut score: SInt32 = 0;
+        let ret = unsafe {
+            IOCreatePlugInInterfaceForService(
+                service,
+                &*type_id as *const CFUUID,
+                &*plugin_id as *const CFUUID,
+                &mut plugin,
+                &mut score,
+            )
+        };
+
+        // A non-SMART-capable device (e.g. a USB bridge) fails here; the caller
+        // turns our `None` into an all-`None` SmartHealth rather than erroring.
+        if ret != 0 || plugin.is_null() {
+            return None;
+        }
+
+        let interface_id = unsafe { ata_smart_interface_id() };
+        let mut iface: *mut *mut IOATASMARTInterface = ptr::null_mut();
+        let qi = match interface_id {
+            Some(interface_id) => unsafe {
+                ((**plugin).query_interface)(
+                    plugin as *mut c_void,
+                    CFUUIDGetUUIDBytes(Some(&interface_id)),
+                    &mut iface as *mut _ as *mut *mut c_void,
+                )
+            },
+            None => -1,
+        };
+
+        unsafe { ((**plugin).release)(plugin as *mut c_void) };
+
+        if qi != 0 || iface.is_null() {
+            return None;
+        }
+
+        let mut health = SmartHealth::default();
+
+        unsafe {
+            // SMART must be enabled before the device will answer queries.
+            let _ = ((**iface).smart_enable_disable_operations)(iface as *mut c_void, 1);
+
+            let mut exceeded: Boolean = 0;
+            if ((**iface).smart_return_status)(iface as *mut c_void, &mut exceeded) == 0 {
+                health.overall_passed = Some(exceeded == 0);
+            }
+
+            let mut data = ATASmartData { data: [0u8; 512] };
+            if ((**iface).smart_read_data)(iface as *mut c_void, &mut data) == 0 {
+                parse_attributes(&data.data, &mut health);
+            }
+
+            ((**iface).release)(iface as *mut c_void);
+        }
+
+        Some(health)
+    }
+}
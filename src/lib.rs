@@ -15,6 +15,17 @@ impl PartialEq for DeviceId {
     }
 }
 
+/// Physical medium backing a device, as reported by the storage controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaType {
+    /// Flash-backed storage (SSD, NVMe, most USB sticks and SD cards).
+    SolidState,
+    /// Spinning magnetic media.
+    Rotational,
+    /// The controller did not report a recognizable medium type.
+    Unknown,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum DeviceKind {
     UsbFlashDrive,
@@ -22,9 +33,20 @@ pub enum DeviceKind {
     MicroSdCard,
     InternalDrive,
     ExternalDrive,
+    /// Removable media exposing a top-level `DCIM` directory, i.e. a digital
+    /// camera or equivalent capture device mounted as mass storage.
+    Camera,
     Other,
 }
 
+/// Verdict returned by a mount-approval predicate. Denying a mount prevents the
+/// volume from being mounted and surfaces `reason` to the system.
+#[derive(Clone, Debug)]
+pub enum MountDecision {
+    Allow,
+    Deny { reason: String },
+}
+
 #[derive(Clone, Debug)]
 pub enum StorageEvent {
     AddDevice {
@@ -67,6 +89,10 @@ pub struct StorageDevice {
 
     pub kind: DeviceKind,
 
+    /// Whether the media is solid-state or rotational, if the controller
+    /// reports it. `None` means the medium type could not be determined.
+    pub media_type: Option<MediaType>,
+
     /// Whether this device is inside of the computer or outside.
     pub internal: Option<bool>,
 
@@ -76,6 +102,14 @@ pub struct StorageDevice {
     /// Whether this device is considered removable or not.
     pub ejectable: Option<bool>,
 
+    /// Whether this device is a mounted disk image (e.g. a `.dmg`) rather than
+    /// real removable hardware. `None` if it could not be determined.
+    pub is_disk_image: Option<bool>,
+
+    /// Whole-disk BSD device name (e.g. `"disk2"`), used to address the device
+    /// for control operations such as [`eject_device`] and health queries.
+    pub bsd_name: Option<String>,
+
     /// Serial number of hardware device hosting the volume, if available
     pub serial: Option<String>,
 
@@ -83,6 +117,25 @@ pub struct StorageDevice {
     pub volumes: HashSet<VolumeId>,
 }
 
+/// Summary of a device's SMART self-assessment. Fields are `None` when the
+/// underlying controller does not report that attribute (or does not support
+/// SMART at all, as is common for USB bridges).
+#[derive(Clone, Debug, Default)]
+pub struct SmartHealth {
+    /// Overall SMART health assessment: `Some(true)` if the drive reports it is
+    /// passing, `Some(false)` if it predicts imminent failure.
+    pub overall_passed: Option<bool>,
+
+    /// Current drive temperature in degrees Celsius.
+    pub temperature_celsius: Option<u32>,
+
+    /// Cumulative powered-on time in hours.
+    pub power_on_hours: Option<u64>,
+
+    /// Count of reallocated sectors, a leading indicator of media wear.
+    pub reallocated_sectors: Option<u64>,
+}
+
 #[derive(Clone, Debug, Hash, Eq, From)]
 pub struct VolumeId(pub(crate) String);
 
@@ -110,9 +163,18 @@ pub struct StorageVolume {
     /// Platform-specific path that references the volume itself
     pub path: Option<PathBuf>,
 
+    /// BSD device name of this volume's slice (e.g. `"disk2s1"`), used to
+    /// address the volume for control operations such as [`mount_volume`] and
+    /// [`unmount_volume`].
+    pub bsd_name: Option<String>,
+
     /// Path(s) where the files on this volume are mounted
     pub mounts: Vec<PathBuf>,
 
+    /// Name of the filesystem on this volume (e.g. `"apfs"`, `"msdos"`,
+    /// `"exfat"`, `"hfs"`), if known.
+    pub file_system: Option<String>,
+
     /// Identifier for the partition on the device
     pub partition_id: Option<String>,
 